@@ -0,0 +1,197 @@
+use anyhow::Result;
+use async_stream::try_stream;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures::Stream;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, SeekFrom};
+
+use crate::decrypt::{build_key_box, decrypt, HEADER_KEY, INFO_KEY};
+use crate::error::Errors;
+use crate::ncmdump::NcmInfo;
+
+/// The chunk size yielded by [`AsyncNcmdump::get_data`].
+const CHUNK_SIZE: usize = 0x8000;
+
+/// An async counterpart to [`Ncmdump`](crate::Ncmdump) over
+/// [`AsyncRead`] + [`AsyncSeek`] sources.
+///
+/// The layout parsing mirrors the synchronous reader; only the IO calls are
+/// awaited, so files living on network or object storage no longer block the
+/// executor thread.
+pub struct AsyncNcmdump<S>
+where
+    S: AsyncRead + AsyncSeek + Unpin,
+{
+    reader: S,
+    key: (u64, u64),
+    info: (u64, u64),
+    image: (u64, u64),
+}
+
+impl<S> AsyncNcmdump<S>
+where
+    S: AsyncRead + AsyncSeek + Unpin,
+{
+    fn check_format(buffer: &[u8]) -> Result<bool> {
+        let (buf, _) = buffer.split_at(std::mem::size_of::<u64>());
+        let temp = u64::from_ne_bytes(buf.try_into()?);
+        Ok(temp == 0x4d41_4446_4e45_5443)
+    }
+
+    fn get_length(buffer: &[u8]) -> Result<u64> {
+        let bytes = buffer.try_into()?;
+        Ok(u32::from_ne_bytes(bytes) as u64)
+    }
+
+    /// Create an `AsyncNcmdump` from an async seekable reader.
+    pub async fn from_reader(mut reader: S) -> Result<Self> {
+        let mut format = [0; 10];
+        reader
+            .read_exact(&mut format)
+            .await
+            .map_err(|_| Errors::InvalidFileType)?;
+        if !Self::check_format(&format)? {
+            return Err(Errors::InvalidFileType.into());
+        }
+
+        let mut key_length_buffer = [0; 4];
+        reader
+            .read_exact(&mut key_length_buffer)
+            .await
+            .map_err(|_| Errors::InvalidKeyLength)?;
+        let key_start = reader.stream_position().await?;
+        let key_length = Self::get_length(&key_length_buffer)?;
+
+        reader.seek(SeekFrom::Current(key_length as i64)).await?;
+        let mut info_length_buffer = [0; 4];
+        reader
+            .read_exact(&mut info_length_buffer)
+            .await
+            .map_err(|_| Errors::InvalidInfoLength)?;
+        let info_start = reader.stream_position().await?;
+        let info_length = Self::get_length(&info_length_buffer)?;
+
+        reader.seek(SeekFrom::Current(info_length as i64)).await?;
+        reader.seek(SeekFrom::Current(9)).await?;
+        let mut image_length_buffer = [0; 4];
+        reader
+            .read_exact(&mut image_length_buffer)
+            .await
+            .map_err(|_| Errors::InvalidImageLength)?;
+        let image_start = reader.stream_position().await?;
+        let image_length = Self::get_length(&image_length_buffer)?;
+
+        Ok(Self {
+            reader,
+            key: (key_start, key_length),
+            info: (info_start, info_length),
+            image: (image_start, image_length),
+        })
+    }
+
+    async fn get_bytes(&mut self, start: u64, length: u64) -> Result<Vec<u8>> {
+        self.reader.seek(SeekFrom::Start(start)).await?;
+        let mut buffer = vec![0; length as usize];
+        self.reader.read_exact(&mut buffer).await?;
+        Ok(buffer)
+    }
+
+    /// Utils for get key.
+    pub async fn get_key(&mut self) -> Result<Vec<u8>> {
+        let (start, length) = self.key;
+        let key = self.get_bytes(start, length).await?;
+        let key_buffer = key.iter().map(|byte| byte ^ 0x64).collect::<Vec<u8>>();
+        let decrypt_buffer = decrypt(&key_buffer, &HEADER_KEY)?;
+        Ok(decrypt_buffer[17..].to_vec())
+    }
+
+    /// Decode the information buffer and just return the information.
+    pub async fn get_info(&mut self) -> Result<NcmInfo> {
+        let (start, length) = self.info;
+        let info_bytes = self.get_bytes(start, length).await?;
+        let info_tmp = info_bytes
+            .iter()
+            .map(|item| item ^ 0x63)
+            .collect::<Vec<u8>>();
+        let info_key = STANDARD
+            .decode(&info_tmp[22..])
+            .map_err(|_| Errors::InfoDecodeError)?;
+        let info_data = decrypt(&info_key, &INFO_KEY)?;
+        let info_str =
+            String::from_utf8(info_data[6..].to_vec()).map_err(|_| Errors::InfoDecodeError)?;
+        let info =
+            serde_json::from_str::<NcmInfo>(&info_str).map_err(|_| Errors::InfoDecodeError)?;
+        Ok(info)
+    }
+
+    /// Get the image bytes from ncmdump, if it's exists.
+    pub async fn get_image(&mut self) -> Result<Vec<u8>> {
+        let (start, length) = self.image;
+        self.get_bytes(start, length).await
+    }
+
+    /// Stream the decrypted music data as a sequence of chunks.
+    ///
+    /// Each yielded chunk is decrypted independently using the position-indexed
+    /// keybox, so no keystream state carries between chunks.
+    pub fn get_data(&mut self) -> impl Stream<Item = Result<Vec<u8>>> + '_ {
+        let start = self.image.0 + self.image.1;
+        try_stream! {
+            let key = self.get_key().await?;
+            let key_box = build_key_box(&key);
+            self.reader.seek(SeekFrom::Start(start)).await?;
+
+            let mut buffer = vec![0; CHUNK_SIZE];
+            loop {
+                // Fill a full chunk so the keystream index stays aligned to the
+                // 0x8000 boundary even when the reader hands back short reads.
+                let mut filled = 0;
+                while filled < CHUNK_SIZE {
+                    let size = self.reader.read(&mut buffer[filled..]).await?;
+                    if size == 0 {
+                        break;
+                    }
+                    filled += size;
+                }
+                if filled == 0 {
+                    break;
+                }
+                let chunk = buffer[..filled]
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        let j = (index + 1) & 0xff;
+                        item ^ key_box[(key_box[j] + key_box[(key_box[j] + j) & 0xff]) & 0xff] as u8
+                    })
+                    .collect::<Vec<u8>>();
+                yield chunk;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use tokio::fs::File as AsyncFile;
+
+    use super::AsyncNcmdump;
+    use crate::Ncmdump;
+
+    #[tokio::test]
+    async fn test_stream_matches_sync_get_data_ok() -> anyhow::Result<()> {
+        let mut sync = Ncmdump::from_reader(std::fs::File::open("./tests/test.ncm")?)?;
+        let expected = sync.get_data()?;
+
+        let mut ncm = AsyncNcmdump::from_reader(AsyncFile::open("./tests/test.ncm").await?).await?;
+        let stream = ncm.get_data();
+        futures::pin_mut!(stream);
+        let mut streamed = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            streamed.extend(chunk?);
+        }
+
+        assert_eq!(streamed, expected);
+        Ok(())
+    }
+}
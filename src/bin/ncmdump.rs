@@ -0,0 +1,120 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use glob::glob;
+use ncmdump::{NcmInfo, Ncmdump};
+use rayon::prelude::*;
+
+/// Decrypt NetEase `.ncm` files in batch.
+#[derive(Debug, Parser)]
+#[command(name = "ncmdump", about, version)]
+struct Command {
+    /// The input files, shell globs such as `./music/**/*.ncm` are expanded.
+    #[arg(required = true)]
+    input: Vec<String>,
+
+    /// The output filename template, expanded from the parsed `NcmInfo`.
+    #[arg(short, long, default_value = "{artist} - {name}.{format}")]
+    output: String,
+
+    /// Print the parsed information as JSON instead of decrypting.
+    #[arg(long)]
+    info_only: bool,
+
+    /// Dump the cover art into this directory alongside the audio.
+    #[arg(long)]
+    image_dir: Option<PathBuf>,
+
+    /// Skip files whose output already exists.
+    #[arg(long)]
+    skip_existing: bool,
+
+    /// The number of worker threads, defaults to the available parallelism.
+    #[arg(short, long)]
+    jobs: Option<usize>,
+}
+
+/// Expand the output template from the track information.
+fn format_name(template: &str, info: &NcmInfo) -> String {
+    let artist = info
+        .artist
+        .first()
+        .map(|(name, _)| name.as_str())
+        .unwrap_or_default();
+    template
+        .replace("{artist}", artist)
+        .replace("{name}", &info.name)
+        .replace("{album}", &info.album)
+        .replace("{format}", &info.format)
+}
+
+/// Decrypt a single file.
+fn dump(path: &Path, command: &Command) -> Result<()> {
+    let mut ncm = Ncmdump::from_reader(File::open(path)?)?;
+    let info = ncm.get_info()?;
+
+    if command.info_only {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    let target = path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(format_name(&command.output, &info));
+    if command.skip_existing && target.exists() {
+        return Ok(());
+    }
+
+    let data = ncm.get_data()?;
+    File::create(&target)?.write_all(&data)?;
+
+    if let Some(dir) = &command.image_dir {
+        let image = ncm.get_image()?;
+        if !image.is_empty() {
+            let stem = target
+                .file_stem()
+                .ok_or_else(|| anyhow!("invalid output name"))?;
+            let extension = if image.starts_with(&[0x89, 0x50, 0x4e, 0x47]) {
+                "png"
+            } else {
+                "jpg"
+            };
+            let mut cover = dir.join(stem);
+            cover.set_extension(extension);
+            File::create(cover)?.write_all(&image)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let command = Command::parse();
+
+    if let Some(dir) = &command.image_dir {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    if let Some(jobs) = command.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()?;
+    }
+
+    let paths = command
+        .input
+        .iter()
+        .flat_map(|pattern| glob(pattern).into_iter().flatten())
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+
+    paths.par_iter().for_each(|path| {
+        if let Err(error) = dump(path, &command) {
+            eprintln!("{}: {error}", path.display());
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::io::{Read, Seek};
+
+use anyhow::Result;
+
+use crate::error::Errors;
+use crate::kuwo::Kuwo;
+use crate::ncmdump::{Ncmdump, NcmInfo};
+use crate::qmc::Qmc;
+
+/// A single encrypted-music container backend.
+///
+/// Every supported DRM format implements this trait so that callers can treat
+/// NetEase `.ncm`, QQ Music QMC and Kuwo `.kwm` files uniformly. Metadata and
+/// cover art are optional because some containers carry neither.
+pub trait Decryptor {
+    /// Whether `header` (the leading bytes of a file) belongs to this format.
+    fn detect(header: &[u8]) -> bool
+    where
+        Self: Sized;
+
+    /// The parsed track information, when the container embeds it.
+    fn info(&mut self) -> Result<Option<NcmInfo>>;
+
+    /// The embedded cover art bytes, or an empty vector when absent.
+    fn image(&mut self) -> Result<Vec<u8>>;
+
+    /// The fully decrypted audio payload.
+    fn data(&mut self) -> Result<Vec<u8>>;
+}
+
+impl<S> Decryptor for Ncmdump<S>
+where
+    S: Read + Seek,
+{
+    fn detect(header: &[u8]) -> bool {
+        header.len() >= 8 && u64::from_ne_bytes(header[..8].try_into().unwrap()) == 0x4d41_4446_4e45_5443
+    }
+
+    fn info(&mut self) -> Result<Option<NcmInfo>> {
+        self.get_info().map(Some)
+    }
+
+    fn image(&mut self) -> Result<Vec<u8>> {
+        self.get_image()
+    }
+
+    fn data(&mut self) -> Result<Vec<u8>> {
+        self.get_data()
+    }
+}
+
+/// Sniff the format from the leading bytes and build the matching decryptor.
+///
+/// The reader is rewound to the start before dispatch, so it may be handed in
+/// at any position.
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs::File;
+///
+/// use anyhow::Result;
+/// use ncmdump::decryptor::open;
+///
+/// fn main() -> Result<()> {
+///     let file = File::open("tests/test.ncm")?;
+///     let mut decryptor = open(file)?;
+///     let _ = decryptor.data()?;
+///     Ok(())
+/// }
+/// ```
+pub fn open<S>(mut reader: S) -> Result<Box<dyn Decryptor>>
+where
+    S: Read + Seek + 'static,
+{
+    use std::io::SeekFrom;
+
+    let mut header = [0; 16];
+    reader.seek(SeekFrom::Start(0))?;
+    let size = reader.read(&mut header)?;
+    reader.seek(SeekFrom::Start(0))?;
+    let header = &header[..size];
+
+    if Ncmdump::<S>::detect(header) {
+        Ok(Box::new(Ncmdump::from_reader(reader)?))
+    } else if Kuwo::detect(header) {
+        Ok(Box::new(Kuwo::from_reader(reader)?))
+    } else if Qmc::detect(header) {
+        Ok(Box::new(Qmc::from_reader(reader)?))
+    } else {
+        Err(Errors::InvalidFileType.into())
+    }
+}
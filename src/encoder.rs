@@ -0,0 +1,133 @@
+use std::io::Write;
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::decrypt::{build_key_box, HEADER_KEY, INFO_KEY};
+use crate::ncmdump::NcmInfo;
+
+/// AES-128-ECB encrypt with PKCS#7 padding.
+///
+/// The inverse of [`crate::decrypt::decrypt`]: the key sections are encrypted
+/// under [`HEADER_KEY`]/[`INFO_KEY`] with the same block cipher the decoder
+/// unwraps them with, so a round-trip reproduces the original ciphertext.
+fn encrypt(data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    use aes::cipher::generic_array::GenericArray;
+    use aes::cipher::{BlockEncrypt, KeyInit};
+    use aes::Aes128;
+
+    let cipher = Aes128::new(GenericArray::from_slice(key));
+    let pad = 16 - (data.len() % 16);
+    let mut buffer = data.to_vec();
+    buffer.extend(std::iter::repeat(pad as u8).take(pad));
+    for block in buffer.chunks_mut(16) {
+        cipher.encrypt_block(GenericArray::from_mut_slice(block));
+    }
+    Ok(buffer)
+}
+
+/// The marker that precedes the RC4 key inside the key section.
+const KEY_MARKER: &[u8] = b"neteasecloudmusic";
+
+/// Build an encrypted `.ncm` container from decrypted audio and metadata.
+///
+/// This is the inverse of [`Ncmdump`](crate::Ncmdump): given the RC4 `key`, the
+/// decrypted `data`, an [`NcmInfo`] and an optional cover `image`, it writes a
+/// stream that [`Ncmdump::from_reader`](crate::Ncmdump::from_reader) can read
+/// back. Passing the key returned by
+/// [`Ncmdump::get_key`](crate::Ncmdump::get_key) yields a file that decodes to
+/// the same info, image and audio. It is not byte-identical to the original:
+/// the info section holds only the [`NcmInfo`] fields, and the two bytes after
+/// the magic and the 9 reserved bytes are written as zeros.
+pub struct NcmEncoder;
+
+impl NcmEncoder {
+    /// Encode into `out`.
+    pub fn encode<W>(
+        mut out: W,
+        key: &[u8],
+        data: &[u8],
+        info: &NcmInfo,
+        image: &[u8],
+    ) -> Result<()>
+    where
+        W: Write,
+    {
+        // Magic.
+        out.write_all(&0x4d41_4446_4e45_5443u64.to_ne_bytes())?;
+        out.write_all(&[0, 0])?;
+
+        // Key section: "neteasecloudmusic" marker + key, AES-ECB encrypted
+        // under HEADER_KEY then XORed with 0x64.
+        let mut key_plain = KEY_MARKER.to_vec();
+        key_plain.extend_from_slice(key);
+        let key_enc = encrypt(&key_plain, &HEADER_KEY)?;
+        let key_section = key_enc.iter().map(|byte| byte ^ 0x64).collect::<Vec<u8>>();
+        out.write_all(&(key_section.len() as u32).to_ne_bytes())?;
+        out.write_all(&key_section)?;
+
+        // Info section: "music:" + JSON, AES-ECB encrypted under INFO_KEY,
+        // base64 encoded, then XORed with 0x63.
+        let json = serde_json::to_string(info)?;
+        let mut info_plain = b"music:".to_vec();
+        info_plain.extend_from_slice(json.as_bytes());
+        let info_enc = encrypt(&info_plain, &INFO_KEY)?;
+        let info_b64 = STANDARD.encode(&info_enc);
+        let mut info_section = b"163 key(Don't modify):".to_vec();
+        info_section.extend_from_slice(info_b64.as_bytes());
+        let info_section = info_section.iter().map(|byte| byte ^ 0x63).collect::<Vec<u8>>();
+        out.write_all(&(info_section.len() as u32).to_ne_bytes())?;
+        out.write_all(&info_section)?;
+
+        // 9 reserved bytes.
+        out.write_all(&[0; 9])?;
+
+        // Image.
+        out.write_all(&(image.len() as u32).to_ne_bytes())?;
+        out.write_all(image)?;
+
+        // Audio, XORed with the position-indexed keybox.
+        let key_box = build_key_box(key);
+        let audio = data
+            .chunks(0x8000)
+            .flat_map(|chunk| {
+                chunk.iter().enumerate().map(|(index, item)| {
+                    let j = (index + 1) & 0xff;
+                    item ^ key_box[(key_box[j] + key_box[(key_box[j] + j) & 0xff]) & 0xff] as u8
+                })
+            })
+            .collect::<Vec<u8>>();
+        out.write_all(&audio)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Cursor;
+
+    use anyhow::Result;
+
+    use super::NcmEncoder;
+    use crate::Ncmdump;
+
+    #[test]
+    fn test_round_trip_decodes_back_ok() -> Result<()> {
+        let mut ncm = Ncmdump::from_reader(File::open("./tests/test.ncm")?)?;
+        let key = ncm.get_key()?;
+        let info = ncm.get_info()?;
+        let image = ncm.get_image()?;
+        let data = ncm.get_data()?;
+
+        let mut encoded = Vec::new();
+        NcmEncoder::encode(&mut encoded, &key, &data, &info, &image)?;
+
+        let mut round = Ncmdump::from_reader(Cursor::new(encoded))?;
+        assert_eq!(round.get_info()?, info);
+        assert_eq!(round.get_image()?, image);
+        assert_eq!(round.get_data()?, data);
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::decrypt::{build_key_box, decrypt, HEADER_KEY, INFO_KEY};
 use crate::error::Errors;
+use crate::reader::NcmReader;
 
 /// The music information
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -222,6 +223,79 @@ where
         Ok(image)
     }
 
+    /// Get a seekable, streaming reader over the decrypted audio data.
+    ///
+    /// The returned [`NcmReader`] implements [`Read`] and [`Seek`], decrypting
+    /// lazily as bytes are pulled rather than buffering the whole payload. This
+    /// is preferable for large files such as FLAC.
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use std::fs::File;
+    /// use std::io::{copy, Seek, SeekFrom};
+    ///
+    /// use anyhow::Result;
+    /// use ncmdump::Ncmdump;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let file = File::open("tests/test.ncm")?;
+    ///     let mut ncm = Ncmdump::from_reader(file)?;
+    ///     let mut reader = ncm.data_reader()?;
+    ///
+    ///     let mut target = File::options()
+    ///         .create(true)
+    ///         .write(true)
+    ///         .open("tests/test.flac")?;
+    ///     copy(&mut reader, &mut target)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn data_reader(&mut self) -> Result<NcmReader<&mut S>> {
+        let key = self.get_key()?;
+        let data_start = self.image.0 + self.image.1;
+        let data_length = self.reader.seek(SeekFrom::End(0))? - data_start;
+        NcmReader::new(self.reader.by_ref(), &key, data_start, data_length)
+    }
+
+    /// Decrypt the audio and write it into a tagged container.
+    ///
+    /// The [`NcmInfo`] fields and the embedded cover image are injected into the
+    /// output, choosing Vorbis comments for FLAC or an ID3v2 frame set for MP3
+    /// based on [`NcmInfo::format`]. This turns the raw dump into a drop-in
+    /// "decrypt + retag" step.
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use std::fs::File;
+    ///
+    /// use anyhow::Result;
+    /// use ncmdump::Ncmdump;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let file = File::open("tests/test.ncm")?;
+    ///     let mut ncm = Ncmdump::from_reader(file)?;
+    ///     let target = File::options()
+    ///         .create(true)
+    ///         .read(true)
+    ///         .write(true)
+    ///         .truncate(true)
+    ///         .open("tests/test.flac")?;
+    ///     ncm.write_tagged(target)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn write_tagged<W>(&mut self, out: W) -> Result<()>
+    where
+        W: std::io::Write + Seek,
+    {
+        let info = self.get_info()?;
+        let image = self.get_image()?;
+        let data = self.get_data()?;
+        crate::tag::embed(out, &data, &info, &image)
+    }
+
     /// Get the music data from ncmdump.
     ///
     /// # Example:
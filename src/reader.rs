@@ -0,0 +1,127 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use anyhow::Result;
+
+/// A seekable, streaming view over the decrypted audio payload.
+///
+/// Unlike [`Ncmdump::get_data`](crate::Ncmdump::get_data), which reads the whole
+/// payload into memory and XOR-decrypts it in one pass, `NcmReader` decrypts
+/// lazily as bytes are pulled through [`Read`]. The keybox XOR is purely
+/// position-dependent, so [`Seek`] is supported without any re-scan: seeking to
+/// output offset `p` simply maps to reader position `data_start + p`.
+pub struct NcmReader<S>
+where
+    S: Read + Seek,
+{
+    reader: S,
+    key_box: [usize; 256],
+    data_start: u64,
+    data_length: u64,
+    position: u64,
+}
+
+impl<S> NcmReader<S>
+where
+    S: Read + Seek,
+{
+    /// Create a reader over the decrypted audio stream.
+    ///
+    /// `key` is the RC4 key returned by [`Ncmdump::get_key`](crate::Ncmdump::get_key),
+    /// `data_start` the absolute offset of the first audio byte and `data_length`
+    /// the number of encrypted bytes that follow it.
+    pub(crate) fn new(mut reader: S, key: &[u8], data_start: u64, data_length: u64) -> Result<Self> {
+        let key_box = crate::decrypt::build_key_box(key);
+        reader.seek(SeekFrom::Start(data_start))?;
+        Ok(Self {
+            reader,
+            key_box,
+            data_start,
+            data_length,
+            position: 0,
+        })
+    }
+
+    /// The keystream byte for output offset `p`.
+    fn key_stream(&self, p: u64) -> u8 {
+        let key_box = &self.key_box;
+        let j = (p as usize + 1) & 0xff;
+        key_box[(key_box[j] + key_box[(key_box[j] + j) & 0xff]) & 0xff] as u8
+    }
+}
+
+impl<S> Read for NcmReader<S>
+where
+    S: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.data_length.saturating_sub(self.position);
+        if remaining == 0 {
+            return Ok(0);
+        }
+        let limit = remaining.min(buf.len() as u64) as usize;
+        let size = self.reader.read(&mut buf[..limit])?;
+        for (offset, byte) in buf[..size].iter_mut().enumerate() {
+            *byte ^= self.key_stream(self.position + offset as u64);
+        }
+        self.position += size as u64;
+        Ok(size)
+    }
+}
+
+impl<S> Seek for NcmReader<S>
+where
+    S: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::End(offset) => self.data_length as i64 + offset,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+        };
+        if target < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            ));
+        }
+        self.position = target as u64;
+        self.reader.seek(SeekFrom::Start(self.data_start + self.position))?;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::{Read, Seek, SeekFrom};
+
+    use anyhow::Result;
+
+    use crate::Ncmdump;
+
+    #[test]
+    fn test_data_reader_matches_get_data_ok() -> Result<()> {
+        let mut ncm = Ncmdump::from_reader(File::open("./tests/test.ncm")?)?;
+        let expected = ncm.get_data()?;
+
+        let mut streamed = Vec::new();
+        ncm.data_reader()?.read_to_end(&mut streamed)?;
+
+        assert_eq!(streamed, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_reader_seek_ok() -> Result<()> {
+        let mut ncm = Ncmdump::from_reader(File::open("./tests/test.ncm")?)?;
+        let expected = ncm.get_data()?;
+
+        let mut reader = ncm.data_reader()?;
+        reader.seek(SeekFrom::Start(1024))?;
+        let mut buffer = [0; 16];
+        reader.read_exact(&mut buffer)?;
+
+        assert_eq!(buffer[..], expected[1024..1040]);
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use std::io::{Seek, Write};
+
+use anyhow::Result;
+
+use crate::error::Errors;
+use crate::ncmdump::NcmInfo;
+
+/// Sniff the image mime type from its leading magic bytes.
+///
+/// Only the two formats NetEase ships cover art as are recognised; anything
+/// else is treated as JPEG, matching the container defaults.
+fn sniff_mime(image: &[u8]) -> &'static str {
+    if image.starts_with(&[0x89, 0x50, 0x4e, 0x47]) {
+        "image/png"
+    } else {
+        "image/jpeg"
+    }
+}
+
+/// Write decrypted audio into a tagged container, picking the format from
+/// [`NcmInfo::format`].
+///
+/// FLAC payloads gain Vorbis comments plus a `METADATA_BLOCK_PICTURE`; MP3
+/// payloads gain an ID3v2 frame set (`TIT2`, `TPE1`, `TALB`) plus an `APIC`
+/// cover frame. The cover `image` may be empty to skip the picture.
+pub fn embed<W>(mut out: W, data: &[u8], info: &NcmInfo, image: &[u8]) -> Result<()>
+where
+    W: Write + Seek,
+{
+    match info.format.as_str() {
+        "flac" => embed_flac(&mut out, data, info, image),
+        "mp3" => embed_mp3(&mut out, data, info, image),
+        _ => Err(Errors::InvalidFileType.into()),
+    }
+}
+
+fn embed_flac<W>(out: &mut W, data: &[u8], info: &NcmInfo, image: &[u8]) -> Result<()>
+where
+    W: Write + Seek,
+{
+    use std::io::Cursor;
+
+    use metaflac::Tag;
+    use metaflac::block::{Block, Picture, PictureType};
+
+    // Parse the leading metadata blocks out of the decrypted stream; the cursor
+    // is left positioned at the first audio frame, which we splice back in after
+    // the grown block set so the frames are never overwritten.
+    let mut cursor = Cursor::new(data);
+    let mut tag = Tag::read_from(&mut cursor)?;
+    {
+        let comment = tag.vorbis_comments_mut();
+        comment.set_title(vec![info.name.clone()]);
+        comment.set_album(vec![info.album.clone()]);
+        if let Some((artist, _)) = info.artist.first() {
+            comment.set_artist(vec![artist.clone()]);
+        }
+    }
+    if !image.is_empty() {
+        let mut picture = Picture::new();
+        picture.picture_type = PictureType::CoverFront;
+        picture.mime_type = sniff_mime(image).to_string();
+        picture.data = image.to_vec();
+        tag.push_block(Block::Picture(picture));
+    }
+    let audio_start = cursor.position() as usize;
+    tag.write_to(out)?;
+    out.write_all(&data[audio_start..])?;
+    Ok(())
+}
+
+fn embed_mp3<W>(out: &mut W, data: &[u8], info: &NcmInfo, image: &[u8]) -> Result<()>
+where
+    W: Write + Seek,
+{
+    use id3::frame::{Picture, PictureType};
+    use id3::{Tag, TagLike, Version};
+
+    let mut tag = Tag::new();
+    tag.set_title(&info.name);
+    tag.set_album(&info.album);
+    if let Some((artist, _)) = info.artist.first() {
+        tag.set_artist(artist);
+    }
+    if !image.is_empty() {
+        tag.add_frame(Picture {
+            mime_type: sniff_mime(image).to_string(),
+            picture_type: PictureType::CoverFront,
+            description: String::new(),
+            data: image.to_vec(),
+        });
+    }
+    tag.write_to(&mut *out, Version::Id3v24)?;
+    out.write_all(data)?;
+    Ok(())
+}